@@ -3,45 +3,77 @@ use std::{error::Error, fmt};
 use chrono::{DateTime, Datelike, Days, FixedOffset, NaiveDate, NaiveDateTime, Utc};
 use error_stack::{Report, Result};
 use ics::{
-    properties::{Categories, DtEnd, DtStart, Location, Summary},
+    parameters::{Role, CN},
+    properties::{
+        Attendee, Categories, Description, DtEnd, DtStart, ExDate, Location, RRule, Summary,
+    },
     Event,
 };
 use itertools::Itertools;
-use uuid::Uuid;
 
 use crate::course::{Class, Course, Exam};
+use crate::filter::FilterSpec;
+
+/// Marks a UID as one this tool generated, as opposed to an event a user
+/// added to the calendar by hand. Used by [`crate::merge`] to tell the two
+/// apart when reconciling with a previously generated calendar.
+pub const GENERATED_UID_PREFIX: &str = "ntu-timetable-ics";
+
+/// Builds a UID that's stable across runs: keyed by course code, class type
+/// and occurrence start, rather than a random UUID. This is what lets
+/// [`crate::merge`] recognize "the same" event across two generated
+/// calendars instead of always treating them as new.
+fn generated_uid(course_code: &str, class_type: &str, start: DateTime<Utc>) -> String {
+    format!(
+        "{}-{}-{}-{}",
+        GENERATED_UID_PREFIX,
+        course_code,
+        class_type,
+        to_rfc5545_datetime_with_utc(start)
+    )
+}
 
 pub fn generate_events(
     courses: &[Course],
     semester_start_date: NaiveDate,
     offset: FixedOffset,
-) -> Vec<Event> {
+    only: Option<&FilterSpec>,
+    skip: Option<&FilterSpec>,
+) -> Vec<Event<'static>> {
     let mut events = Vec::new();
     for course in courses.iter() {
         for class in course.classes.iter() {
+            if !passes_filter(class, only, skip) {
+                continue;
+            }
             events.append(
-                &mut generate_class_events(
-                    course.code.clone(),
-                    course.title.clone(),
-                    class,
-                    semester_start_date,
-                    offset,
-                )
-                .unwrap(),
+                &mut generate_class_events(course, class, semester_start_date, offset).unwrap(),
             );
         }
         if let Some(exam) = &course.exam {
-            events.push(generate_exam_event(
-                course.code.clone(),
-                course.title.clone(),
-                exam,
-                offset,
-            ))
+            events.push(generate_exam_event(course, exam, offset))
         }
     }
     events
 }
 
+/// Whether `class` should be included given the `--only`/`--skip` filters:
+/// `only` keeps just classes it matches, `skip` drops classes it matches.
+/// Exams aren't subject to either, since they don't recur on a weekday.
+fn passes_filter(class: &Class, only: Option<&FilterSpec>, skip: Option<&FilterSpec>) -> bool {
+    if let Some(only) = only {
+        if !only.matches(class) {
+            return false;
+        }
+    }
+    if let Some(skip) = skip {
+        if skip.matches(class) {
+            return false;
+        }
+    }
+    true
+}
+
 #[derive(Debug)]
 pub struct DateTimeError;
 
@@ -54,14 +86,18 @@ impl fmt::Display for DateTimeError {
 impl Error for DateTimeError {}
 
 pub fn generate_class_events(
-    course_code: String,
-    course_title: String,
+    course: &Course,
     class: &Class,
     semester_start_date: NaiveDate,
     offset: FixedOffset,
-) -> Result<Vec<Event>, DateTimeError> {
-    let event_title = format!("{} - {} {}", course_code, course_title, class.class_type);
-    let semester_start_date = NaiveDate::from_isoywd_opt(
+) -> Result<Vec<Event<'static>>, DateTimeError> {
+    let course_code = course.code.clone();
+    let event_title = format!("{} - {} {}", course.code, course.title, class.class_type);
+    let description = format!(
+        "AU: {} | Type: {} | Index: {} | Group: {} | Status: {}",
+        course.au, course.course_type, course.index, class.group, course.status
+    );
+    let week_start_date = NaiveDate::from_isoywd_opt(
         semester_start_date.year(),
         semester_start_date.iso_week().week(),
         class.weekday,
@@ -73,49 +109,144 @@ pub fn generate_class_events(
         class.weekday.number_from_sunday()
     )))?;
 
-    Ok(class
-        .weeks
+    let mut weeks = class.weeks.clone();
+    weeks.sort_unstable();
+    weeks.dedup();
+
+    let missing_weeks: Vec<u32> = match (weeks.first(), weeks.last()) {
+        (Some(&min), Some(&max)) => (min..=max).filter(|w| !weeks.contains(w)).collect(),
+        _ => Vec::new(),
+    };
+
+    // If punching holes would take more EXDATEs than there are actual
+    // occurrences, a single RRULE is no longer a useful compression: fall
+    // back to one event per taught week.
+    if weeks.is_empty() || missing_weeks.len() > weeks.len() {
+        return Ok(generate_per_week_events(
+            &event_title,
+            &description,
+            &course_code,
+            class,
+            week_start_date,
+            offset,
+            &weeks,
+        ));
+    }
+
+    let occurrence_start = |w: u32| -> DateTime<Utc> {
+        let date = week_start_date
+            .checked_add_days(Days::new((w - 1) as u64 * 7))
+            .unwrap();
+        convert_naive_to_utc_datetime(NaiveDateTime::new(date, class.period.start), offset)
+    };
+
+    let first_week = *weeks.first().unwrap();
+    let last_week = *weeks.last().unwrap();
+
+    let first_occurrence_date = week_start_date
+        .checked_add_days(Days::new((first_week - 1) as u64 * 7))
+        .unwrap();
+    let start_datetime = convert_naive_to_utc_datetime(
+        NaiveDateTime::new(first_occurrence_date, class.period.start),
+        offset,
+    );
+    let end_datetime = convert_naive_to_utc_datetime(
+        NaiveDateTime::new(first_occurrence_date, class.period.end),
+        offset,
+    );
+
+    let mut event = Event::new(
+        generated_uid(&course_code, &class.class_type, start_datetime),
+        to_rfc5545_datetime_with_utc(Utc::now()),
+    );
+    event.push(Summary::new(event_title));
+    event.push(Description::new(description));
+    event.push(DtStart::new(to_rfc5545_datetime_with_utc(start_datetime)));
+    event.push(DtEnd::new(to_rfc5545_datetime_with_utc(end_datetime)));
+    event.push(Categories::new(class.class_type.clone()));
+    event.push(Location::new(class.venue.clone()));
+    if !class.instructor.is_empty() {
+        event.push(instructor_attendee(&class.instructor));
+    }
+    event.push(RRule::new(format!(
+        "FREQ=WEEKLY;INTERVAL=1;UNTIL={}",
+        to_rfc5545_datetime_with_utc(occurrence_start(last_week))
+    )));
+    for w in missing_weeks {
+        event.push(ExDate::new(to_rfc5545_datetime_with_utc(occurrence_start(
+            w,
+        ))));
+    }
+
+    Ok(vec![event])
+}
+
+fn generate_per_week_events<'a>(
+    event_title: &str,
+    description: &str,
+    course_code: &str,
+    class: &Class,
+    week_start_date: NaiveDate,
+    offset: FixedOffset,
+    weeks: &[u32],
+) -> Vec<Event<'a>> {
+    weeks
         .iter()
         .map(|w| {
-            let mut event = Event::new(
-                format!("{}-{}", course_code, Uuid::new_v4()),
-                to_rfc5545_datetime_with_utc(Utc::now()),
-            );
-            let date = semester_start_date
+            let date = week_start_date
                 .checked_add_days(Days::new((w - 1) as u64 * 7))
                 .unwrap();
             let start_datetime =
                 convert_naive_to_utc_datetime(NaiveDateTime::new(date, class.period.start), offset);
             let end_datetime =
                 convert_naive_to_utc_datetime(NaiveDateTime::new(date, class.period.end), offset);
-            event.push(Summary::new(event_title.clone()));
+            let mut event = Event::new(
+                generated_uid(course_code, &class.class_type, start_datetime),
+                to_rfc5545_datetime_with_utc(Utc::now()),
+            );
+            event.push(Summary::new(event_title.to_string()));
+            event.push(Description::new(description.to_string()));
             event.push(DtStart::new(to_rfc5545_datetime_with_utc(start_datetime)));
             event.push(DtEnd::new(to_rfc5545_datetime_with_utc(end_datetime)));
             event.push(Categories::new(class.class_type.clone()));
             event.push(Location::new(class.venue.clone()));
+            if !class.instructor.is_empty() {
+                event.push(instructor_attendee(&class.instructor));
+            }
             event
         })
-        .collect_vec())
+        .collect_vec()
 }
 
-pub fn generate_exam_event(
-    course_code: String,
-    course_title: String,
-    exam: &Exam,
-    offset: FixedOffset,
-) -> Event {
+/// Builds an `ATTENDEE` property marking `instructor` as the chairperson of
+/// the class. RFC 5545 requires the property value to be a CAL-ADDRESS URI,
+/// and there's no instructor email in the source table, so we use a
+/// `mailto:` placeholder address and carry the real name in `CN`.
+fn instructor_attendee(instructor: &str) -> Attendee<'static> {
+    let mut attendee = Attendee::new("mailto:noreply@invalid".to_string());
+    attendee.add(CN::new(instructor.to_string()));
+    attendee.add(Role::CHAIR);
+    attendee
+}
+
+pub fn generate_exam_event(course: &Course, exam: &Exam, offset: FixedOffset) -> Event<'static> {
+    let start_datetime =
+        convert_naive_to_utc_datetime(NaiveDateTime::new(exam.date, exam.peroid.start), offset);
+    let end_datetime =
+        convert_naive_to_utc_datetime(NaiveDateTime::new(exam.date, exam.peroid.end), offset);
+
     let mut event = Event::new(
-        format!("{}-{}", course_code, Uuid::new_v4()),
+        generated_uid(&course.code, "EXAM", start_datetime),
         to_rfc5545_datetime_with_utc(Utc::now()),
     );
     event.push(Summary::new(format!(
         "{} - {} Exam",
-        course_code, course_title
+        course.code, course.title
+    )));
+    event.push(Description::new(format!(
+        "AU: {} | Type: {} | Index: {} | Status: {}",
+        course.au, course.course_type, course.index, course.status
     )));
-    let start_datetime =
-        convert_naive_to_utc_datetime(NaiveDateTime::new(exam.date, exam.peroid.start), offset);
-    let end_datetime =
-        convert_naive_to_utc_datetime(NaiveDateTime::new(exam.date, exam.peroid.end), offset);
     event.push(DtStart::new(to_rfc5545_datetime_with_utc(start_datetime)));
     event.push(DtEnd::new(to_rfc5545_datetime_with_utc(end_datetime)));
     event.push(Categories::new("Exam"));
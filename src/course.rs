@@ -27,6 +27,10 @@ pub struct Class {
     pub group: String,
     pub weeks: Vec<u32>,
     pub class_type: String,
+    /// Instructor name, if the table included one. Empty when the column
+    /// was blank, the export format doesn't carry this information, or the
+    /// value doesn't look like a name (see [`plausible_instructor`]).
+    pub instructor: String,
 }
 
 #[derive(Debug)]
@@ -112,6 +116,7 @@ impl Course {
                 group: row[10].into(),
                 weeks: parse_weeks(row[14], recess_week).change_context(ParseTableError::Other)?,
                 class_type: row[9].into(),
+                instructor: plausible_instructor(row[8]),
             };
 
             if let Some(current_course) = courses.last_mut() {
@@ -127,6 +132,22 @@ impl Course {
     }
 }
 
+/// Column 8 isn't confirmed to be the instructor in every export variant, so
+/// treat it cautiously: values that don't look like a name (blank, numeric,
+/// or a status-style placeholder such as "TBA"/"NIL") are reported as absent
+/// rather than risk stamping a bogus instructor onto the event.
+fn plausible_instructor(raw: &str) -> String {
+    let raw = raw.trim();
+    let looks_like_name = !raw.is_empty()
+        && raw.chars().any(|c| c.is_alphabetic())
+        && !matches!(raw.to_uppercase().as_str(), "NIL" | "TBA" | "N/A" | "-");
+    if looks_like_name {
+        raw.to_string()
+    } else {
+        String::new()
+    }
+}
+
 #[derive(Debug)]
 pub struct ParseExamError;
 
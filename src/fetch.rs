@@ -0,0 +1,73 @@
+//! Fetches the raw timetable straight from the NTU student portal, so a
+//! student doesn't have to manually copy-paste the tab-delimited blob out of
+//! the browser into a file first.
+//!
+//! The portal renders the timetable as an HTML `<table>`; this module
+//! reconstructs the same 16-column tab-separated rows that
+//! [`crate::course::Course::parse_from_table`] already expects from a pasted
+//! file, so the two input paths converge on one parser.
+
+use error_stack::{IntoReport, ResultExt};
+use itertools::Itertools;
+use scraper::{Html, Selector};
+
+use crate::course::ParseTableError;
+
+/// Spoofed to dodge the portal's bot/rate-limiting checks on plain `reqwest`
+/// user agents.
+const USER_AGENT: &str =
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36";
+
+const TIMETABLE_TABLE_SELECTOR: &str = "table#timetableTable";
+
+/// Fetches `url` and reconstructs the tab-separated table that
+/// `Course::parse_from_table` expects.
+pub fn fetch_timetable_table(url: &str) -> error_stack::Result<String, ParseTableError> {
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(USER_AGENT)
+        .build()
+        .into_report()
+        .change_context(ParseTableError::Other)
+        .attach_printable("Failed to build HTTP client")?;
+
+    let body = client
+        .get(url)
+        .send()
+        .into_report()
+        .change_context(ParseTableError::Other)
+        .attach_printable_lazy(|| format!("Failed to fetch timetable from {}", url))?
+        .text()
+        .into_report()
+        .change_context(ParseTableError::Other)
+        .attach_printable("Failed to read response body")?;
+
+    extract_table(&body)
+}
+
+fn extract_table(body: &str) -> error_stack::Result<String, ParseTableError> {
+    let document = Html::parse_document(body);
+    let table_selector = Selector::parse(TIMETABLE_TABLE_SELECTOR).unwrap();
+    let row_selector = Selector::parse("tr").unwrap();
+    // `td` only: the portal's header row is made of `th` cells, and pulling
+    // those into the same tab stream would hand `parse_from_table` a bogus
+    // 16-cell chunk (or shift every subsequent field if the header's cell
+    // count doesn't happen to also be 16).
+    let cell_selector = Selector::parse("td").unwrap();
+
+    let table = document.select(&table_selector).next().ok_or_else(|| {
+        error_stack::Report::new(ParseTableError::Other).attach_printable(format!(
+            "Could not find timetable element matching `{}`",
+            TIMETABLE_TABLE_SELECTOR
+        ))
+    })?;
+
+    Ok(table
+        .select(&row_selector)
+        .map(|row| {
+            row.select(&cell_selector)
+                .map(|cell| cell.text().collect::<String>().trim().to_string())
+                .join("\t")
+        })
+        .filter(|row| !row.is_empty())
+        .join("\t"))
+}
@@ -0,0 +1,149 @@
+//! A compact spec for selecting which classes end up in the generated
+//! calendar, e.g. `--only "mon..fri 0800-1800"` to keep just daytime
+//! weekday classes, or `--skip "sat,sun"` to drop weekend labs.
+//!
+//! A spec is made of up to two whitespace-separated parts, in either order:
+//! a comma-separated weekday list (`mon`, or a `mon..fri` range) and an
+//! `HHMM-HHMM` time window. Either part can be omitted, defaulting to "all
+//! days"/"all times".
+
+use std::fmt;
+
+use chrono::{NaiveTime, Weekday};
+use regex::Regex;
+
+use crate::course::{Class, Period};
+
+const WEEKDAY_NAMES: [(&str, Weekday); 7] = [
+    ("mon", Weekday::Mon),
+    ("tue", Weekday::Tue),
+    ("wed", Weekday::Wed),
+    ("thu", Weekday::Thu),
+    ("fri", Weekday::Fri),
+    ("sat", Weekday::Sat),
+    ("sun", Weekday::Sun),
+];
+
+/// A set of weekdays, stored as a 7-bit mask (bit 0 = Monday).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekDays(u8);
+
+impl WeekDays {
+    pub const ALL: WeekDays = WeekDays(0b0111_1111);
+    pub const NONE: WeekDays = WeekDays(0);
+
+    fn insert(&mut self, day: Weekday) {
+        self.0 |= 1 << day.num_days_from_monday();
+    }
+
+    pub fn contains(&self, day: Weekday) -> bool {
+        self.0 & (1 << day.num_days_from_monday()) != 0
+    }
+}
+
+/// An `HHMM-HHMM` time-of-day window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HmTime {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl HmTime {
+    fn contains(&self, period: &Period) -> bool {
+        period.start >= self.start && period.end <= self.end
+    }
+}
+
+/// A parsed `--only`/`--skip` filter expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterSpec {
+    days: WeekDays,
+    time: Option<HmTime>,
+}
+
+impl FilterSpec {
+    /// Whether `class` falls inside this spec's weekday set and (if given)
+    /// time window.
+    pub fn matches(&self, class: &Class) -> bool {
+        self.days.contains(class.weekday) && self.time.is_none_or(|t| t.contains(&class.period))
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseFilterSpecError(String);
+
+impl fmt::Display for ParseFilterSpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid filter spec: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseFilterSpecError {}
+
+/// Parses a filter expression like `"mon..fri 0800-1800"` into a
+/// [`FilterSpec`]. Used directly as a clap `value_parser`, hence the
+/// `String` error.
+pub fn parse_filter_spec(spec: &str) -> Result<FilterSpec, String> {
+    let time_re = Regex::new(r"^(\d{2})(\d{2})-(\d{2})(\d{2})$").unwrap();
+    let mut days = None;
+    let mut time = None;
+    for token in spec.split_whitespace() {
+        if let Some(captures) = time_re.captures(token) {
+            if time.replace(parse_time_window(&captures)?).is_some() {
+                return Err(format!("Duplicate time window in spec: {}", spec));
+            }
+        } else if days.replace(parse_weekdays(token)?).is_some() {
+            return Err(format!("Duplicate weekday list in spec: {}", spec));
+        }
+    }
+    Ok(FilterSpec {
+        days: days.unwrap_or(WeekDays::ALL),
+        time,
+    })
+}
+
+fn parse_time_window(captures: &regex::Captures) -> Result<HmTime, String> {
+    let start_hour: u32 = captures[1].parse().unwrap();
+    let start_minute: u32 = captures[2].parse().unwrap();
+    let end_hour: u32 = captures[3].parse().unwrap();
+    let end_minute: u32 = captures[4].parse().unwrap();
+    let start = NaiveTime::from_hms_opt(start_hour, start_minute, 0)
+        .ok_or_else(|| format!("Invalid start time: {:02}{:02}", start_hour, start_minute))?;
+    let end = NaiveTime::from_hms_opt(end_hour, end_minute, 0)
+        .ok_or_else(|| format!("Invalid end time: {:02}{:02}", end_hour, end_minute))?;
+    Ok(HmTime { start, end })
+}
+
+fn parse_weekdays(token: &str) -> Result<WeekDays, String> {
+    let mut days = WeekDays::NONE;
+    for part in token.split(',') {
+        match part.split_once("..") {
+            Some((start, end)) => {
+                for day in weekday_range(weekday_from_name(start)?, weekday_from_name(end)?)? {
+                    days.insert(day);
+                }
+            }
+            None => days.insert(weekday_from_name(part)?),
+        }
+    }
+    Ok(days)
+}
+
+fn weekday_from_name(name: &str) -> Result<Weekday, String> {
+    WEEKDAY_NAMES
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, day)| *day)
+        .ok_or_else(|| format!("Unknown weekday: {}", name))
+}
+
+fn weekday_range(start: Weekday, end: Weekday) -> Result<Vec<Weekday>, String> {
+    let start_idx = start.num_days_from_monday();
+    let end_idx = end.num_days_from_monday();
+    if end_idx < start_idx {
+        return Err(format!("Descending weekday range: {}..{}", start, end));
+    }
+    Ok((start_idx..=end_idx)
+        .map(|i| WEEKDAY_NAMES[i as usize].1)
+        .collect())
+}
@@ -0,0 +1,198 @@
+//! Renders the parsed timetable as a standalone HTML page: a Monday-Sunday
+//! weekly grid, colour-coded by `class_type`, so a student can sanity-check
+//! their schedule before importing the generated `.ics` file.
+
+use std::collections::HashSet;
+
+use chrono::{NaiveDate, NaiveTime, Timelike, Weekday};
+use itertools::Itertools;
+
+use crate::course::Course;
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+const DEFAULT_START_HOUR: u32 = 8;
+const DEFAULT_END_HOUR: u32 = 18;
+
+struct TimetableEntry {
+    weekday: Weekday,
+    start: NaiveTime,
+    end: NaiveTime,
+    class_type: String,
+    label: String,
+}
+
+/// Renders `courses` as a weekly grid HTML page.
+///
+/// The grid has one row per hour and buckets each class under its start
+/// hour, so a class starting at a half hour still renders in that hour's
+/// row. Classes that are simultaneously active in a given weekday/hour cell
+/// (whether they share a start hour, or one starts inside another's span)
+/// are folded into that cell's label rather than any of them being dropped.
+///
+/// When `public` is `true`, venue, index and group details are stripped and
+/// every class is rendered as a generic "Busy" block, so the page can be
+/// shared without leaking room or course information.
+pub fn generate_html(courses: &[Course], semester_start_date: NaiveDate, public: bool) -> String {
+    let entries = collect_entries(courses, public);
+    let (earliest_hour, latest_hour) = time_bounds(&entries);
+
+    let mut body = String::new();
+    let mut covered: HashSet<(Weekday, u32)> = HashSet::new();
+    for hour in earliest_hour..latest_hour {
+        body.push_str(&format!("    <tr>\n      <th>{:02}:00</th>\n", hour));
+        for weekday in WEEKDAYS {
+            if covered.remove(&(weekday, hour)) {
+                continue;
+            }
+            let active = active_entries(&entries, weekday, hour);
+            if active.is_empty() {
+                body.push_str("      <td></td>\n");
+                continue;
+            }
+            // Extend the cell for as long as the same set of classes stays
+            // active, so a class that clashes partway through another one's
+            // span still gets folded into the cell instead of vanishing.
+            let mut rowspan = 1;
+            while hour + rowspan < latest_hour
+                && active_entries(&entries, weekday, hour + rowspan) == active
+            {
+                rowspan += 1;
+            }
+            for h in hour + 1..hour + rowspan {
+                covered.insert((weekday, h));
+            }
+            let label = active
+                .iter()
+                .map(|&i| entries[i].label.as_str())
+                .join("<hr>");
+            body.push_str(&format!(
+                "      <td rowspan=\"{}\" class=\"class-{}\">{}</td>\n",
+                rowspan,
+                css_class(&entries[active[0]].class_type),
+                label
+            ));
+        }
+        body.push_str("    </tr>\n");
+    }
+
+    let headers = WEEKDAYS
+        .iter()
+        .map(|w| format!("      <th>{}</th>", w))
+        .join("\n");
+
+    format!(
+        "<!DOCTYPE html>\n\
+<html lang=\"en\">\n\
+<head>\n\
+  <meta charset=\"utf-8\">\n\
+  <title>Timetable Preview</title>\n\
+  <style>{}</style>\n\
+</head>\n\
+<body>\n\
+  <h1>Timetable - week starting {}</h1>\n\
+  <table>\n\
+    <tr>\n\
+      <th></th>\n\
+{}\n\
+    </tr>\n\
+{}\
+  </table>\n\
+</body>\n\
+</html>\n",
+        STYLE, semester_start_date, headers, body
+    )
+}
+
+fn collect_entries(courses: &[Course], public: bool) -> Vec<TimetableEntry> {
+    let mut entries = Vec::new();
+    for course in courses {
+        for class in &course.classes {
+            entries.push(if public {
+                TimetableEntry {
+                    weekday: class.weekday,
+                    start: class.period.start,
+                    end: class.period.end,
+                    class_type: "BUSY".to_string(),
+                    label: "Busy".to_string(),
+                }
+            } else {
+                TimetableEntry {
+                    weekday: class.weekday,
+                    start: class.period.start,
+                    end: class.period.end,
+                    class_type: class.class_type.clone(),
+                    label: format!(
+                        "<strong>{}</strong><br>{} &middot; {}",
+                        course.code, class.class_type, class.venue
+                    ),
+                }
+            });
+        }
+    }
+    entries
+}
+
+fn entry_rowspan(entry: &TimetableEntry) -> u32 {
+    let minutes = (entry.end - entry.start).num_minutes().max(60);
+    (minutes as u32).div_ceil(60)
+}
+
+/// Indices (into `entries`) of the classes still running during `hour` on
+/// `weekday` — not just the ones starting at `hour`, so a class is counted
+/// as active for every hour row its span covers.
+fn active_entries(entries: &[TimetableEntry], weekday: Weekday, hour: u32) -> Vec<usize> {
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| {
+            e.weekday == weekday
+                && e.start.hour() <= hour
+                && hour < e.start.hour() + entry_rowspan(e)
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn time_bounds(entries: &[TimetableEntry]) -> (u32, u32) {
+    let earliest = entries
+        .iter()
+        .map(|e| e.start.hour())
+        .min()
+        .unwrap_or(DEFAULT_START_HOUR);
+    let latest = entries
+        .iter()
+        .map(|e| e.end.hour() + u32::from(e.end.minute() > 0))
+        .max()
+        .unwrap_or(DEFAULT_END_HOUR);
+    (
+        earliest.min(DEFAULT_START_HOUR),
+        latest.max(DEFAULT_END_HOUR),
+    )
+}
+
+fn css_class(class_type: &str) -> String {
+    match class_type.to_uppercase().as_str() {
+        "LEC" => "lec".to_string(),
+        "TUT" => "tut".to_string(),
+        "LAB" => "lab".to_string(),
+        _ => "other".to_string(),
+    }
+}
+
+const STYLE: &str = "
+table { border-collapse: collapse; width: 100%; }
+th, td { border: 1px solid #ccc; padding: 4px; text-align: center; vertical-align: top; }
+.class-lec { background: #cfe8ff; }
+.class-tut { background: #ffe8b3; }
+.class-lab { background: #d4f7d4; }
+.class-other { background: #e8e8e8; }
+";
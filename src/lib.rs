@@ -0,0 +1,6 @@
+pub mod cal;
+pub mod course;
+pub mod fetch;
+pub mod filter;
+pub mod html;
+pub mod merge;
@@ -6,12 +6,20 @@ use ics::ICalendar;
 use ntu_timetable_ics::{
     cal::generate_events,
     course::{Course, ParseTableError},
+    fetch::fetch_timetable_table,
+    filter::{parse_filter_spec, FilterSpec},
+    html::generate_html,
+    merge::read_user_added_events,
 };
 
 #[derive(Parser, Debug)]
+#[command(allow_missing_positional = true)]
 struct Args {
-    /// input timetable file
-    file: String,
+    /// input timetable file, pasted from the NTU portal (mutually exclusive with --url)
+    file: Option<String>,
+    /// fetch the timetable directly from this NTU portal URL instead of reading a file
+    #[arg(long)]
+    url: Option<String>,
     /// date containing starting week of semester
     #[arg(value_parser = is_valid_date)]
     semester_start_date: chrono::NaiveDate,
@@ -23,29 +31,70 @@ struct Args {
     out: String,
     /// reccess week number
     #[arg(short, long, default_value_t = 8)]
-    recess_week: u32
+    recess_week: u32,
+    /// also render an HTML timetable preview to this path
+    #[arg(long)]
+    html: Option<String>,
+    /// strip venue/index details from the HTML preview and show generic "Busy" blocks
+    #[arg(long)]
+    public: bool,
+    /// merge with a previously generated calendar, keeping events added by hand
+    #[arg(long)]
+    merge: Option<String>,
+    /// only export classes matching this weekday/time-window spec, e.g. "mon..fri 0800-1800"
+    #[arg(long, value_parser = parse_filter_spec)]
+    only: Option<FilterSpec>,
+    /// exclude classes matching this weekday/time-window spec, e.g. "sat,sun"
+    #[arg(long, value_parser = parse_filter_spec)]
+    skip: Option<FilterSpec>,
 }
 
 fn main() {
     env_logger::init();
     let args = Args::parse();
-    let mut f = File::open(args.file).expect("Failed to open timetable file");
-    let mut table = String::new();
-    f.read_to_string(&mut table).expect("Failed to read file");
-    let courses = Course::parse_from_table(table, args.recess_week);
+    let table = match (&args.file, &args.url) {
+        (Some(file), None) => {
+            let mut f = File::open(file).expect("Failed to open timetable file");
+            let mut table = String::new();
+            f.read_to_string(&mut table).expect("Failed to read file");
+            Ok(table)
+        }
+        (None, Some(url)) => fetch_timetable_table(url),
+        _ => {
+            eprintln!("Provide exactly one of <file> or --url");
+            std::process::exit(1);
+        }
+    };
+    let courses = table.and_then(|table| Course::parse_from_table(table, args.recess_week));
     match courses {
         Ok(courses) => {
             let offset = FixedOffset::east_opt(args.minutes_offset * 60).expect("Invalid offset");
             let mut calendar = ICalendar::new("1.0", "ntu-ics");
 
-            generate_events(&courses, args.semester_start_date, offset)
-                .into_iter()
-                .for_each(|e| calendar.add_event(e));
+            let mut events = generate_events(
+                &courses,
+                args.semester_start_date,
+                offset,
+                args.only.as_ref(),
+                args.skip.as_ref(),
+            );
+            if let Some(merge_path) = &args.merge {
+                let user_added = read_user_added_events(merge_path)
+                    .expect("Failed to read calendar to merge with");
+                events.extend(user_added);
+            }
+            events.into_iter().for_each(|e| calendar.add_event(e));
             calendar
                 .save_file(args.out.clone())
                 .expect("Failed to save calendar");
 
             println!("Saved calendar to: {}", args.out);
+
+            if let Some(html_out) = &args.html {
+                let html = generate_html(&courses, args.semester_start_date, args.public);
+                std::fs::write(html_out, html).expect("Failed to save HTML preview");
+                println!("Saved HTML preview to: {}", html_out);
+            }
         }
         Err(err) => {
             match err.current_context() {
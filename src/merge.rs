@@ -0,0 +1,88 @@
+//! Reconciles a freshly generated calendar with one produced by an earlier
+//! run, so re-running the tool mid-semester (after a class moves or is
+//! cancelled) doesn't wipe out events the user added by hand or duplicate
+//! ones that are still current.
+//!
+//! Generated events carry a deterministic UID (see
+//! [`crate::cal::generated_uid`]) keyed by course code, class type and start
+//! datetime. Re-generating the calendar from the latest table already
+//! produces the up-to-date set of "our" events, so merging only needs to
+//! carry forward whatever the existing file has that *isn't* one of ours.
+
+use std::{fs::File, io::BufReader};
+
+use error_stack::{IntoReport, ResultExt};
+use ical::parser::ical::component::IcalEvent;
+use ics::{
+    components::{Parameter, Property},
+    Event,
+};
+
+use crate::{cal::GENERATED_UID_PREFIX, course::ParseTableError};
+
+/// Reads the VEVENTs of a previously generated `.ics` file at `path` and
+/// returns only the ones that aren't recognized as generated by this tool,
+/// i.e. events the user added by hand. Generated events are recognized by
+/// their UID prefix alone, so hand edits made to one of them are not kept.
+pub fn read_user_added_events(path: &str) -> error_stack::Result<Vec<Event<'static>>, ParseTableError> {
+    let file = File::open(path)
+        .into_report()
+        .change_context(ParseTableError::Other)
+        .attach_printable_lazy(|| format!("Failed to open existing calendar at {}", path))?;
+
+    let mut events = Vec::new();
+    for calendar in ical::IcalParser::new(BufReader::new(file)) {
+        let calendar = calendar
+            .into_report()
+            .change_context(ParseTableError::Other)
+            .attach_printable_lazy(|| format!("Failed to parse existing calendar at {}", path))?;
+        events.extend(
+            calendar
+                .events
+                .into_iter()
+                .filter(|event| !is_generated(event))
+                .map(to_ics_event),
+        );
+    }
+    Ok(events)
+}
+
+fn is_generated(event: &IcalEvent) -> bool {
+    event
+        .properties
+        .iter()
+        .find(|property| property.name == "UID")
+        .and_then(|property| property.value.as_deref())
+        .is_some_and(|uid| uid.starts_with(GENERATED_UID_PREFIX))
+}
+
+/// Converts a parsed `IcalEvent` back into a writable [`Event`], carrying
+/// every property and parameter (including `TZID`) over untouched.
+fn to_ics_event(ical_event: IcalEvent) -> Event<'static> {
+    let uid = property_value(&ical_event, "UID").unwrap_or_default();
+    let dtstamp = property_value(&ical_event, "DTSTAMP").unwrap_or_default();
+
+    let mut event = Event::new(uid, dtstamp);
+    for property in ical_event.properties {
+        if property.name == "UID" || property.name == "DTSTAMP" {
+            continue;
+        }
+        let Some(value) = property.value else {
+            continue;
+        };
+        let mut prop = Property::new(property.name, value);
+        for (key, values) in property.params.into_iter().flatten() {
+            prop.add(Parameter::new(key, values.join(",")));
+        }
+        event.push(prop);
+    }
+    event
+}
+
+fn property_value(event: &IcalEvent, name: &str) -> Option<String> {
+    event
+        .properties
+        .iter()
+        .find(|property| property.name == name)
+        .and_then(|property| property.value.clone())
+}